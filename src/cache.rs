@@ -0,0 +1,65 @@
+//! A simple on-disk page cache keyed by URL, under `/.net/cache/`.
+//!
+//! Each cached entry stores the decoded response body alongside the `ETag` and
+//! `Last-Modified` response headers it arrived with, so the next fetch of
+//! the same URL can issue a conditional request and reuse the cached body on
+//! a `304 Not Modified`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+const CACHE_DIR: &str = "/.net/cache";
+
+/// A cached response.
+pub struct CacheEntry {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+fn path_for(url: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    Path::new(CACHE_DIR).join(format!("{:016x}.cache", hasher.finish()))
+}
+
+/// Load the cached entry for `url`, if any.
+pub fn load(url: &str) -> Option<CacheEntry> {
+    let content = fs::read_to_string(path_for(url)).ok()?;
+    let (header_block, body) = content.split_once("\n\n")?;
+
+    let mut etag = None;
+    let mut last_modified = None;
+    for line in header_block.lines() {
+        if let Some(value) = line.strip_prefix("ETag: ") {
+            etag = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("Last-Modified: ") {
+            last_modified = Some(value.to_string());
+        }
+    }
+
+    Some(CacheEntry {
+        etag,
+        last_modified,
+        body: body.to_string(),
+    })
+}
+
+/// Store (or overwrite) the cached entry for `url`.
+pub fn store(url: &str, etag: Option<&str>, last_modified: Option<&str>, body: &str) {
+    let mut header_block = String::new();
+    if let Some(etag) = etag {
+        header_block.push_str(&format!("ETag: {}\n", etag));
+    }
+    if let Some(last_modified) = last_modified {
+        header_block.push_str(&format!("Last-Modified: {}\n", last_modified));
+    }
+    // Always separate the header block from the body with a blank line,
+    // even when there are no headers to cache.
+    let content = format!("{}\n\n{}", header_block.trim_end_matches('\n'), body);
+
+    let _ = fs::create_dir_all(CACHE_DIR);
+    let _ = fs::write(path_for(url), content);
+}
@@ -0,0 +1,128 @@
+//! Character-encoding detection and decoding.
+//!
+//! The file-IPC response body arrives as raw bytes; this module figures out
+//! which charset those bytes are actually in (rather than assuming UTF-8)
+//! and decodes them into a proper Rust `String`.
+
+/// Detect the charset for `body`, preferring an explicit `Content-Type`
+/// charset parameter, then a `<meta charset>`/`<meta http-equiv>` tag in the
+/// first ~1KB of the document, and finally falling back to UTF-8.
+pub fn detect_charset(content_type: Option<&str>, body: &[u8]) -> String {
+    let from_header = content_type.and_then(charset_from_content_type);
+    if let Some(charset) = from_header {
+        return charset;
+    }
+
+    let scan_len = body.len().min(1024);
+    if let Some(charset) = charset_from_meta_tag(&body[..scan_len]) {
+        return charset;
+    }
+
+    "utf-8".to_string()
+}
+
+/// Decode `body` using the named charset.
+///
+/// Single-byte Windows-1252/Latin-1 are decoded via their code tables; any
+/// other label (including `utf-8`, and anything unrecognized) falls back to
+/// lossy UTF-8 decoding.
+pub fn decode(body: &[u8], charset: &str) -> String {
+    match charset.to_lowercase().as_str() {
+        "windows-1252" | "cp1252" | "x-cp1252" => decode_windows_1252(body),
+        "iso-8859-1" | "latin1" | "latin-1" | "l1" => decode_latin1(body),
+        _ => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+fn charset_from_content_type(content_type: &str) -> Option<String> {
+    let lower = content_type.to_lowercase();
+    let start = lower.find("charset=")? + "charset=".len();
+    let value = &content_type[start..];
+    let value = value.split(';').next().unwrap_or(value);
+    Some(value.trim().trim_matches('"').trim_matches('\'').to_string())
+}
+
+/// Scan for `<meta charset="...">` or
+/// `<meta http-equiv="Content-Type" content="...charset=...">`.
+///
+/// The `charset=` search is scoped to `<meta ...>` tags (rather than the
+/// whole head), so a `charset=` that happens to appear in ordinary text or
+/// an unrelated attribute isn't mistaken for the page's declared charset.
+/// A direct `<meta charset=...>` tag is preferred over the http-equiv form.
+fn charset_from_meta_tag(head: &[u8]) -> Option<String> {
+    let haystack = String::from_utf8_lossy(head).to_lowercase();
+    let tags = find_meta_tags(&haystack);
+
+    let direct = tags.iter().find(|tag| !tag.contains("http-equiv") && tag.contains("charset="));
+    let http_equiv = tags.iter().find(|tag| tag.contains("http-equiv") && tag.contains("charset="));
+    let tag = direct.or(http_equiv)?;
+
+    let start = tag.find("charset=")? + "charset=".len();
+    let rest = tag[start..].trim_start_matches(['"', '\'']);
+    let end = rest.find(['"', '\'', ';', '>', ' ']).unwrap_or(rest.len());
+    let value = rest[..end].trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Find the text of every `<meta ...>` tag in `haystack`, in document order.
+fn find_meta_tags(haystack: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut offset = 0;
+    while let Some(start_rel) = haystack[offset..].find("<meta") {
+        let tag_start = offset + start_rel;
+        let Some(end_rel) = haystack[tag_start..].find('>') else {
+            break;
+        };
+        let tag_end = tag_start + end_rel;
+        tags.push(&haystack[tag_start..=tag_end]);
+        offset = tag_end + 1;
+    }
+    tags
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+/// Windows-1252 differs from Latin-1 only in the 0x80-0x9F range, where it
+/// assigns printable characters (curly quotes, the euro sign, etc.) instead
+/// of the C1 control codes Latin-1 uses there.
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80 => '\u{20AC}',
+            0x82 => '\u{201A}',
+            0x83 => '\u{0192}',
+            0x84 => '\u{201E}',
+            0x85 => '\u{2026}',
+            0x86 => '\u{2020}',
+            0x87 => '\u{2021}',
+            0x88 => '\u{02C6}',
+            0x89 => '\u{2030}',
+            0x8A => '\u{0160}',
+            0x8B => '\u{2039}',
+            0x8C => '\u{0152}',
+            0x8E => '\u{017D}',
+            0x91 => '\u{2018}',
+            0x92 => '\u{2019}',
+            0x93 => '\u{201C}',
+            0x94 => '\u{201D}',
+            0x95 => '\u{2022}',
+            0x96 => '\u{2013}',
+            0x97 => '\u{2014}',
+            0x98 => '\u{02DC}',
+            0x99 => '\u{2122}',
+            0x9A => '\u{0161}',
+            0x9B => '\u{203A}',
+            0x9C => '\u{0153}',
+            0x9E => '\u{017E}',
+            0x9F => '\u{0178}',
+            other => other as char,
+        })
+        .collect()
+}
@@ -7,47 +7,246 @@
 
 use std::fs;
 use std::io;
+use std::io::Read as _;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+mod cache;
+mod encoding;
+mod url;
+
 fn main() {
     println!("╔══════════════════════════════════════════════════════════════╗");
     println!("║                    🌐 rustOS Browser v1.0                    ║");
     println!("╚══════════════════════════════════════════════════════════════╝");
     println!();
 
-    // Default URL to fetch
-    let url = "https://httpbin.org/html";
+    // A redirect chain spans several process runs (each fetch crosses the
+    // file-IPC boundary and exits), so resume from persisted state if we're
+    // mid-chain rather than re-parsing argv.
+    let in_flight = match load_redirect_state() {
+        Ok(state) => state,
+        Err(e) => {
+            println!("❌ Failed to read redirect state: {}", e);
+            return;
+        }
+    };
+
+    const MAX_REDIRECT_HOPS: u32 = 10;
+
+    let (mut pending, no_follow, no_cache, hops_left) = match in_flight {
+        Some(state) => (
+            PendingRequest {
+                method: state.method,
+                url: state.url,
+                headers: state.headers,
+                body: state.body,
+                raw: state.raw,
+            },
+            state.no_follow,
+            state.no_cache,
+            state.hops_left,
+        ),
+        None => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            match parse_cli_request(&args) {
+                Ok(request) => {
+                    let no_follow = request.no_follow;
+                    let no_cache = request.no_cache;
+                    (
+                        PendingRequest {
+                            method: request.method,
+                            url: request.url,
+                            headers: request.headers,
+                            body: request.body,
+                            raw: request.raw,
+                        },
+                        no_follow,
+                        no_cache,
+                        MAX_REDIRECT_HOPS,
+                    )
+                }
+                Err(e) => {
+                    println!("❌ {}", e);
+                    println!();
+                    print_usage();
+                    return;
+                }
+            }
+        }
+    };
+
+    pending.url = match url::normalize(&pending.url) {
+        Ok(url) => url,
+        Err(e) => {
+            println!("❌ Invalid URL: {}", e);
+            clear_redirect_state();
+            return;
+        }
+    };
 
-    println!("📍 URL: {}", url);
+    println!("📍 {} {}", pending.method, pending.url);
+    if !pending.headers.is_empty() {
+        for (name, value) in &pending.headers {
+            println!("   {}: {}", name, value);
+        }
+    }
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
-    match http_get(url) {
+    let cacheable = pending.method == "GET" && !no_cache;
+    let cached = if cacheable { cache::load(&pending.url) } else { None };
+
+    let mut request_headers = pending.headers.clone();
+    if let Some(entry) = &cached {
+        if let Some(etag) = &entry.etag {
+            request_headers.push(("If-None-Match".to_string(), etag.clone()));
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            request_headers.push(("If-Modified-Since".to_string(), last_modified.clone()));
+        }
+    }
+
+    match http_request(&pending.method, &pending.url, &request_headers, pending.body.as_deref()) {
         Ok(response) => {
             if let Some(err) = &response.error {
                 println!("❌ Error: {}", err);
+                clear_redirect_state();
                 return;
             }
 
+            let redirect_location = if is_redirect(response.status) && !no_follow {
+                find_header(&response.headers, "Location")
+            } else {
+                None
+            };
+
+            if let Some(location) = redirect_location {
+                if hops_left == 0 {
+                    println!("❌ Too many redirects (stopped after 10 hops)");
+                    clear_redirect_state();
+                    return;
+                }
+
+                let next_url = match url::resolve(&pending.url, location) {
+                    Ok(next_url) => next_url,
+                    Err(e) => {
+                        println!("❌ Bad redirect target: {}", e);
+                        clear_redirect_state();
+                        return;
+                    }
+                };
+                println!("↪ {} → {}", response.status, next_url);
+
+                // 301/302/303 convert the retry to a GET and drop the body;
+                // 307/308 must preserve method and body.
+                if matches!(response.status, 301..=303) {
+                    pending.method = "GET".to_string();
+                    pending.body = None;
+                }
+                pending.url = next_url;
+
+                save_redirect_state(&RedirectState {
+                    method: pending.method.clone(),
+                    url: pending.url.clone(),
+                    headers: pending.headers.clone(),
+                    body: pending.body.clone(),
+                    hops_left: hops_left - 1,
+                    raw: pending.raw,
+                    no_follow,
+                    no_cache,
+                });
+
+                // Issuing the next hop writes a fresh request.json and exits.
+                let _ = http_request(&pending.method, &pending.url, &pending.headers, pending.body.as_deref());
+                return;
+            }
+
+            clear_redirect_state();
+
+            let body = if response.status == 304 {
+                match &cached {
+                    Some(entry) => {
+                        println!("💾 304 Not Modified — using cached copy");
+                        entry.body.clone()
+                    }
+                    None => response.body.clone(),
+                }
+            } else {
+                if cacheable && (200..300).contains(&response.status) {
+                    let etag = find_header(&response.headers, "ETag");
+                    let last_modified = find_header(&response.headers, "Last-Modified");
+                    cache::store(&pending.url, etag, last_modified, &response.body);
+                }
+                response.body.clone()
+            };
+
             println!("✅ Status: {}", response.status);
-            println!("📦 Content Length: {} bytes", response.body.len());
+            println!("📦 Content Length: {} bytes", body.len());
             println!();
+
+            if pending.raw {
+                println!("━━━━━━━━━━━━━━━━━━━━ Raw Body ━━━━━━━━━━━━━━━━━━━━━━");
+                println!();
+                println!("{}", body);
+                return;
+            }
+
             println!("━━━━━━━━━━━━━━━━━━━ Page Content ━━━━━━━━━━━━━━━━━━━");
             println!();
 
-            // Convert HTML to readable text
-            let text = html_to_text(&response.body);
-            
+            // Convert HTML to readable text, resolving links against this page
+            let (text, links) = html_to_text(&body, &pending.url);
+
             // Limit output for terminal readability
             let max_chars = 2000;
-            if text.len() > max_chars {
-                println!("{}", &text[..max_chars]);
+            let char_count = text.chars().count();
+            if char_count > max_chars {
+                let truncated: String = text.chars().take(max_chars).collect();
+                println!("{}", truncated);
                 println!();
-                println!("... (truncated, {} more characters)", text.len() - max_chars);
+                println!("... (truncated, {} more characters)", char_count - max_chars);
             } else {
                 println!("{}", text);
             }
+
+            if links.is_empty() {
+                return;
+            }
+
+            println!();
+            println!("━━━━━━━━━━━━━━━━━━━━━━━ Links ━━━━━━━━━━━━━━━━━━━━━━━");
+            for link in &links {
+                let label = if link.text.is_empty() { &link.url } else { &link.text };
+                println!("[{}] {}", link.index, label);
+            }
+            println!();
+            println!("Enter a link number to follow, or press Enter to quit:");
+
+            let mut choice = String::new();
+            if io::stdin().read_line(&mut choice).is_err() {
+                return;
+            }
+            let Ok(choice) = choice.trim().parse::<usize>() else {
+                return;
+            };
+            let Some(link) = links.iter().find(|l| l.index == choice) else {
+                println!("❌ No such link: {}", choice);
+                return;
+            };
+
+            save_redirect_state(&RedirectState {
+                method: "GET".to_string(),
+                url: link.url.clone(),
+                headers: Vec::new(),
+                body: None,
+                hops_left: MAX_REDIRECT_HOPS,
+                raw: pending.raw,
+                no_follow,
+                no_cache,
+            });
+            let _ = http_request("GET", &link.url, &[], None);
+            return;
         }
         Err(e) => {
             println!("❌ Request failed: {}", e);
@@ -62,6 +261,138 @@ fn main() {
     println!("🏁 Browser session ended.");
 }
 
+/// A request in progress, independent of how it was produced (argv or a
+/// resumed redirect chain).
+struct PendingRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    raw: bool,
+}
+
+fn print_usage() {
+    println!("Usage: browser [METHOD] [URL] [BODY] [-H \"Name: Value\"]... [--raw] [--no-follow] [--no-cache]");
+    println!();
+    println!("  METHOD       GET, POST, PUT, PATCH, or DELETE (default: GET)");
+    println!("  URL          target URL (default: https://httpbin.org/html)");
+    println!("  BODY         request body; pass \"-\" to read it from stdin");
+    println!("  -H           add a request header, may be repeated");
+    println!("  --raw        print the raw response body instead of rendering it");
+    println!("  --no-follow  print 3xx responses as-is instead of following Location");
+    println!("  --no-cache   skip the local page cache and conditional requests");
+    println!();
+    println!("Example: browser POST https://httpbin.org/post '{{\"a\":1}}' -H \"Content-Type: application/json\"");
+}
+
+// ============================================================================
+// Command-line argument parsing
+// ============================================================================
+
+/// A request built from argv, ready to hand to `http_request`.
+struct CliRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    raw: bool,
+    no_follow: bool,
+    no_cache: bool,
+}
+
+const KNOWN_METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Parse `argv` (already stripped of argv[0]) into a `CliRequest`.
+///
+/// Accepts an optional leading METHOD, then a URL, then an optional BODY,
+/// interleaved with `-H "Name: Value"` and `--raw`/`--no-follow`/`--no-cache`
+/// flags in any order.
+fn parse_cli_request(args: &[String]) -> Result<CliRequest, String> {
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut body: Option<String> = None;
+    let mut headers = Vec::new();
+    let mut raw = false;
+    let mut no_follow = false;
+    let mut no_cache = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+
+        if arg == "--raw" {
+            raw = true;
+            i += 1;
+            continue;
+        }
+
+        if arg == "--no-follow" {
+            no_follow = true;
+            i += 1;
+            continue;
+        }
+
+        if arg == "--no-cache" {
+            no_cache = true;
+            i += 1;
+            continue;
+        }
+
+        if arg == "-H" {
+            let value = args.get(i + 1).ok_or("-H requires a \"Name: Value\" argument")?;
+            let (name, value) = value
+                .split_once(':')
+                .ok_or_else(|| format!("invalid header (expected \"Name: Value\"): {}", value))?;
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+            i += 2;
+            continue;
+        }
+
+        if method.is_none() && KNOWN_METHODS.contains(&arg.to_uppercase().as_str()) {
+            method = Some(arg.to_uppercase());
+            i += 1;
+            continue;
+        }
+
+        if url.is_none() {
+            url = Some(arg.clone());
+        } else if body.is_none() {
+            body = Some(arg.clone());
+        } else {
+            return Err(format!("unexpected argument: {}", arg));
+        }
+        i += 1;
+    }
+
+    let url = match url {
+        Some(url) => url,
+        None => "https://httpbin.org/html".to_string(),
+    };
+
+    let body = match body {
+        Some(b) if b == "-" => Some(read_stdin_to_string()?),
+        other => other,
+    };
+
+    Ok(CliRequest {
+        method: method.unwrap_or_else(|| "GET".to_string()),
+        url,
+        headers,
+        body,
+        raw,
+        no_follow,
+        no_cache,
+    })
+}
+
+fn read_stdin_to_string() -> Result<String, String> {
+    let mut buf = String::new();
+    io::stdin()
+        .read_to_string(&mut buf)
+        .map_err(|e| format!("failed to read body from stdin: {}", e))?;
+    Ok(buf)
+}
+
 // ============================================================================
 // HTTP Module (file-based IPC with rustOS host)
 // ============================================================================
@@ -70,18 +401,42 @@ fn main() {
 struct HttpResponse {
     status: u16,
     body: String,
+    headers: Vec<(String, String)>,
     error: Option<String>,
 }
 
-/// Perform an HTTP GET request via rustOS file-based IPC
-fn http_get(url: &str) -> io::Result<HttpResponse> {
+/// Is `status` one of the redirect codes we know how to follow?
+fn is_redirect(status: u16) -> bool {
+    matches!(status, 301 | 302 | 303 | 307 | 308)
+}
+
+/// Case-insensitive lookup of a response header.
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+
+/// Perform an HTTP request via rustOS file-based IPC.
+///
+/// `headers` and `body` are serialized into `request.json` verbatim; pass an
+/// empty slice / `None` for a plain GET with no extra headers.
+fn http_request(
+    method: &str,
+    url: &str,
+    headers: &[(String, String)],
+    body: Option<&str>,
+) -> io::Result<HttpResponse> {
     let net_dir = Path::new("/.net");
     let request_file = net_dir.join("request.json");
     let response_file = net_dir.join("response.json");
 
-    // Check if we already have a response (from a previous run)
+    // Check if we already have a response (from a previous run). Read as
+    // raw bytes: the body may be in an encoding that isn't valid UTF-8.
     if response_file.exists() {
-        let response_content = fs::read_to_string(&response_file)?;
+        let response_content = fs::read(&response_file)?;
         let _ = fs::remove_file(&response_file); // Clean up
         return parse_response(&response_content);
     }
@@ -92,16 +447,26 @@ fn http_get(url: &str) -> io::Result<HttpResponse> {
     // Ensure .net directory exists
     fs::create_dir_all(net_dir)?;
 
-    // Write request as JSON
+    let headers_json = headers
+        .iter()
+        .map(|(name, value)| format!("\"{}\":\"{}\"", json_escape(name), json_escape(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let body_json = match body {
+        Some(b) => format!("\"{}\"", json_escape(b)),
+        None => "null".to_string(),
+    };
+
     let request_json = format!(
-        r#"{{"id":"{}","method":"GET","url":"{}","headers":{{}},"body":null}}"#,
-        request_id, url
+        r#"{{"id":"{}","method":"{}","url":"{}","headers":{{{}}},"body":{}}}"#,
+        request_id, method, url, headers_json, body_json
     );
 
     fs::write(&request_file, request_json)?;
 
     // Exit the app - rustOS will process the request and re-run us
-    println!("[NET] Fetching: {}", url);
+    println!("[NET] {} {}", method, url);
     println!("[NET] Waiting for response from rustOS host...");
     std::process::exit(0);
 }
@@ -113,18 +478,225 @@ fn generate_request_id() -> String {
     format!("req_{}", now.as_millis())
 }
 
-fn parse_response(content: &str) -> io::Result<HttpResponse> {
-    let status = extract_json_u16(content, "status").unwrap_or(0);
-    let body = extract_json_string(content, "body").unwrap_or_default();
-    let error = extract_json_string(content, "error");
+fn parse_response(content: &[u8]) -> io::Result<HttpResponse> {
+    // Everything except the body is plain ASCII JSON, so a lossy UTF-8 view
+    // is safe to use for locating those fields even if the body itself
+    // isn't valid UTF-8.
+    let text = String::from_utf8_lossy(content);
+    let status = extract_json_u16(&text, "status").unwrap_or(0);
+    let headers = extract_json_object(&text, "headers");
+    let error = extract_json_string(&text, "error");
+
+    let mut raw_body = extract_json_bytes(content, "body").unwrap_or_default();
+
+    let is_chunked = find_header(&headers, "Transfer-Encoding")
+        .map(|v| v.to_lowercase().contains("chunked"))
+        .unwrap_or(false);
+    if is_chunked {
+        raw_body = decode_chunked(&raw_body);
+    }
+
+    let content_type = find_header(&headers, "Content-Type");
+    let charset = encoding::detect_charset(content_type, &raw_body);
+    let body = encoding::decode(&raw_body, &charset);
 
     Ok(HttpResponse {
         status,
         body,
+        headers,
         error,
     })
 }
 
+/// Like `extract_json_string`, but returns the raw, unescaped bytes instead
+/// of assuming the value is valid UTF-8 — needed because the body may be in
+/// an encoding other than UTF-8.
+fn extract_json_bytes(json: &[u8], key: &str) -> Option<Vec<u8>> {
+    let pattern = format!("\"{}\":\"", key);
+    let pattern = pattern.as_bytes();
+    let start = json.windows(pattern.len()).position(|w| w == pattern)?;
+    let value_start = start + pattern.len();
+    let remaining = &json[value_start..];
+
+    let mut out = Vec::with_capacity(remaining.len());
+    let mut i = 0;
+    while i < remaining.len() {
+        match remaining[i] {
+            b'"' => break,
+            b'\\' => {
+                let escaped = *remaining.get(i + 1)?;
+                out.push(match escaped {
+                    b'n' => b'\n',
+                    b'r' => b'\r',
+                    b't' => b'\t',
+                    other => other,
+                });
+                i += 2;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    Some(out)
+}
+
+/// Decode an HTTP chunked-transfer-encoded body.
+///
+/// Each chunk is a hex size line (optional `;ext` params are ignored),
+/// `\r\n`, that many bytes of data, then `\r\n`. A zero-size chunk ends the
+/// body; any trailer headers that follow it are discarded. Tolerates a
+/// missing final CRLF and aborts with whatever was decoded so far on a
+/// malformed hex length or a truncated chunk.
+fn decode_chunked(bytes: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut i = 0;
+
+    while let Some((size_end, data_start)) = find_crlf_line_end(bytes, i) {
+        let size_line = &bytes[i..size_end];
+        let size_len = size_line.iter().position(|&b| b == b';').unwrap_or(size_line.len());
+        let size_str = std::str::from_utf8(&size_line[..size_len]).unwrap_or("").trim();
+
+        let size = match usize::from_str_radix(size_str, 16) {
+            Ok(size) => size,
+            Err(_) => break,
+        };
+        if size == 0 {
+            break;
+        }
+
+        let data_end = data_start + size;
+        if data_end > bytes.len() {
+            output.extend_from_slice(&bytes[data_start..]);
+            break;
+        }
+        output.extend_from_slice(&bytes[data_start..data_end]);
+
+        i = data_end;
+        if bytes.get(i) == Some(&b'\r') {
+            i += 1;
+        }
+        if bytes.get(i) == Some(&b'\n') {
+            i += 1;
+        }
+    }
+
+    output
+}
+
+/// Find the end of a `\r\n`- or `\n`-terminated line starting at `start`.
+///
+/// Returns `(line_content_end, line_after_terminator)`.
+fn find_crlf_line_end(bytes: &[u8], start: usize) -> Option<(usize, usize)> {
+    let newline = start + bytes[start..].iter().position(|&b| b == b'\n')?;
+    let content_end = if newline > start && bytes[newline - 1] == b'\r' {
+        newline - 1
+    } else {
+        newline
+    };
+    Some((content_end, newline + 1))
+}
+
+/// Extract a flat `"key":{"Name":"Value",...}` object as a list of pairs.
+///
+/// Values are assumed to be plain strings, which matches the header objects
+/// the rustOS host sends back. Both the object boundary and the individual
+/// strings are scanned with escape/string-state awareness, since header
+/// values (e.g. a quoted `ETag`, or a `}` in a cookie or CSP value) can
+/// contain the very characters used as JSON delimiters.
+fn extract_json_object(json: &str, key: &str) -> Vec<(String, String)> {
+    let pattern = format!("\"{}\":{{", key);
+    let Some(start) = json.find(&pattern) else {
+        return Vec::new();
+    };
+
+    let body_start = start + pattern.len();
+    let Some(end) = find_object_end(&json[body_start..]) else {
+        return Vec::new();
+    };
+    let body = &json[body_start..body_start + end];
+
+    let mut pairs = Vec::new();
+    let mut rest = body;
+    while let Some(name_start) = rest.find('"') {
+        let Some((name, after_name_end)) = scan_json_string(&rest[name_start..]) else {
+            break;
+        };
+        let after_name = &rest[name_start..][after_name_end..];
+        let Some(colon) = after_name.find(':') else {
+            break;
+        };
+        let after_colon = &after_name[colon + 1..];
+        let Some(value_start) = after_colon.find('"') else {
+            break;
+        };
+        let Some((value, after_value_end)) = scan_json_string(&after_colon[value_start..]) else {
+            break;
+        };
+
+        pairs.push((name, value));
+        rest = &after_colon[value_start..][after_value_end..];
+    }
+
+    pairs
+}
+
+/// Find the byte offset of the `}` that closes an object whose body is `s`
+/// (i.e. the text right after the object's opening `{`), tracking string
+/// state and nested-brace depth so a `}` inside a quoted value doesn't end
+/// the scan early.
+fn find_object_end(s: &str) -> Option<usize> {
+    let mut depth = 0u32;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Scan a JSON string literal starting at the beginning of `s` (which must
+/// be `"`), returning its unescaped value and the byte offset of the first
+/// character after the closing quote.
+fn scan_json_string(s: &str) -> Option<(String, usize)> {
+    let mut chars = s.char_indices();
+    let (_, first) = chars.next()?;
+    if first != '"' {
+        return None;
+    }
+    let mut escaped = false;
+    for (i, c) in chars {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '"' => return Some((unescape_json(&s[1..i]), i + 1)),
+            _ => {}
+        }
+    }
+    None
+}
+
 fn extract_json_string(json: &str, key: &str) -> Option<String> {
     let pattern = format!("\"{}\":\"", key);
     if let Some(start) = json.find(&pattern) {
@@ -187,18 +759,131 @@ fn unescape_json(s: &str) -> String {
         .replace("\\t", "\t")
 }
 
+/// Escape a string for embedding inside a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Redirect state (persisted across the file-IPC exit/re-run boundary)
+// ============================================================================
+
+const REDIRECT_STATE_FILE: &str = "/.net/redirect_state.json";
+
+/// State carried between process runs while following a redirect chain.
+struct RedirectState {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    hops_left: u32,
+    raw: bool,
+    no_follow: bool,
+    no_cache: bool,
+}
+
+fn save_redirect_state(state: &RedirectState) {
+    let headers_json = state
+        .headers
+        .iter()
+        .map(|(name, value)| format!("\"{}\":\"{}\"", json_escape(name), json_escape(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let body_json = match &state.body {
+        Some(b) => format!("\"{}\"", json_escape(b)),
+        None => "null".to_string(),
+    };
+
+    let json = format!(
+        r#"{{"method":"{}","url":"{}","headers":{{{}}},"body":{},"hops_left":{},"raw":{},"no_follow":{},"no_cache":{}}}"#,
+        state.method,
+        json_escape(&state.url),
+        headers_json,
+        body_json,
+        state.hops_left,
+        state.raw,
+        state.no_follow,
+        state.no_cache
+    );
+
+    let _ = fs::create_dir_all("/.net");
+    let _ = fs::write(REDIRECT_STATE_FILE, json);
+}
+
+fn load_redirect_state() -> io::Result<Option<RedirectState>> {
+    let path = Path::new(REDIRECT_STATE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)?;
+    let method = extract_json_string(&content, "method").unwrap_or_else(|| "GET".to_string());
+    let url = extract_json_string(&content, "url").unwrap_or_default();
+    let headers = extract_json_object(&content, "headers");
+    let body = extract_json_string(&content, "body");
+    let hops_left = extract_json_u16(&content, "hops_left").unwrap_or(0) as u32;
+    let raw = content.contains("\"raw\":true");
+    let no_follow = content.contains("\"no_follow\":true");
+    let no_cache = content.contains("\"no_cache\":true");
+
+    Ok(Some(RedirectState {
+        method,
+        url,
+        headers,
+        body,
+        hops_left,
+        raw,
+        no_follow,
+        no_cache,
+    }))
+}
+
+fn clear_redirect_state() {
+    let _ = fs::remove_file(REDIRECT_STATE_FILE);
+}
+
 // ============================================================================
 // HTML to Text Converter
 // ============================================================================
 
-/// Convert HTML to readable plain text
-fn html_to_text(html: &str) -> String {
+/// A hyperlink extracted from the page, numbered in the order it appears.
+struct PageLink {
+    index: usize,
+    url: String,
+    text: String,
+}
+
+/// In-progress `<a href>` whose text is still being collected.
+struct OpenAnchor {
+    /// `None` means the anchor has no usable destination (missing/unsafe
+    /// href) and should be inlined as plain text with no `[n]` marker.
+    href: Option<String>,
+    text_start: usize,
+}
+
+/// Convert HTML to readable plain text, annotating hyperlinks with `[n]`
+/// markers resolved against `base_url`.
+fn html_to_text(html: &str, base_url: &str) -> (String, Vec<PageLink>) {
     let mut result = String::new();
     let mut in_tag = false;
     let mut in_script = false;
     let mut in_style = false;
     let mut tag_name = String::new();
     let mut last_char_was_space = false;
+    let mut links: Vec<PageLink> = Vec::new();
+    let mut open_anchor: Option<OpenAnchor> = None;
 
     let chars: Vec<char> = html.chars().collect();
     let mut i = 0;
@@ -216,7 +901,12 @@ fn html_to_text(html: &str) -> String {
         if in_tag {
             if c == '>' {
                 in_tag = false;
-                let tag_lower = tag_name.to_lowercase();
+                let trimmed = tag_name.trim();
+                let (keyword, attrs) = match trimmed.find(char::is_whitespace) {
+                    Some(split) => (&trimmed[..split], &trimmed[split..]),
+                    None => (trimmed, ""),
+                };
+                let tag_lower = keyword.to_lowercase();
 
                 // Handle block-level elements
                 if tag_lower == "br" || tag_lower == "br/" || tag_lower == "br /" {
@@ -244,6 +934,23 @@ fn html_to_text(html: &str) -> String {
                     in_style = false;
                 }
 
+                // Track anchors to number their links
+                if tag_lower == "a" {
+                    let href = extract_attr(attrs, "href").and_then(|href| resolve_link_href(base_url, &href));
+                    open_anchor = Some(OpenAnchor {
+                        href,
+                        text_start: result.len(),
+                    });
+                } else if tag_lower == "/a" {
+                    let href = open_anchor.take().and_then(|anchor| anchor.href.map(|url| (anchor.text_start, url)));
+                    if let Some((text_start, url)) = href {
+                        let text = result[text_start..].trim().to_string();
+                        let index = links.len() + 1;
+                        result.push_str(&format!("[{}]", index));
+                        links.push(PageLink { index, url, text });
+                    }
+                }
+
                 tag_name.clear();
             } else {
                 tag_name.push(c);
@@ -300,7 +1007,38 @@ fn html_to_text(html: &str) -> String {
         }
     }
 
-    cleaned
+    (cleaned, links)
+}
+
+/// Resolve an anchor's `href` against the current page, skipping schemes
+/// that aren't navigable pages.
+fn resolve_link_href(base_url: &str, href: &str) -> Option<String> {
+    let lower = href.trim().to_lowercase();
+    if lower.is_empty() || lower.starts_with("javascript:") || lower.starts_with("mailto:") {
+        return None;
+    }
+    url::resolve(base_url, href).ok()
+}
+
+/// Extract a `name="value"` (or unquoted `name=value`) attribute from a tag's
+/// attribute text.
+fn extract_attr(attrs: &str, name: &str) -> Option<String> {
+    let lower = attrs.to_lowercase();
+    let pattern = format!("{}=", name);
+    let start = lower.find(&pattern)? + pattern.len();
+    let rest = &attrs[start..];
+
+    match rest.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let value_start = quote.len_utf8();
+            let end = rest[value_start..].find(quote)?;
+            Some(rest[value_start..value_start + end].to_string())
+        }
+        _ => {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            Some(rest[..end].to_string())
+        }
+    }
 }
 
 /// Decode common HTML entities
@@ -340,3 +1078,32 @@ fn decode_html_entity(entity: &str) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_chunked_joins_chunks_and_stops_at_terminator() {
+        let input = b"4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        assert_eq!(decode_chunked(input), b"Wikipedia");
+    }
+
+    #[test]
+    fn decode_chunked_ignores_chunk_extensions() {
+        let input = b"4;ext=1\r\nWiki\r\n0\r\n\r\n";
+        assert_eq!(decode_chunked(input), b"Wiki");
+    }
+
+    #[test]
+    fn decode_chunked_tolerates_missing_final_crlf() {
+        let input = b"4\r\nWiki\r\n0";
+        assert_eq!(decode_chunked(input), b"Wiki");
+    }
+
+    #[test]
+    fn decode_chunked_aborts_with_partial_result_on_malformed_hex() {
+        let input = b"4\r\nWiki\r\nzz\r\npedia\r\n0\r\n\r\n";
+        assert_eq!(decode_chunked(input), b"Wiki");
+    }
+}
@@ -0,0 +1,304 @@
+//! Minimal RFC 3986 URL parsing, normalization, and reference resolution.
+//!
+//! Sanitizes user-supplied targets before they are written into
+//! `request.json`, and resolves relative `Location:` headers and in-page
+//! links against the page they came from.
+
+/// A parsed, normalized absolute URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    pub scheme: String,
+    pub host: String,
+    pub port: Option<u16>,
+    pub path: String,
+    pub query: Option<String>,
+    pub fragment: Option<String>,
+}
+
+impl Url {
+    fn has_authority(&self) -> bool {
+        !self.host.is_empty()
+    }
+}
+
+impl std::fmt::Display for Url {
+    /// Render back to a normalized absolute URL string.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        write!(f, "{}", self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse and normalize an absolute (or scheme-less) URL.
+///
+/// Defaults a missing scheme to `https`, lowercases the host, removes `.`/`..`
+/// path segments, and percent-encodes unsafe path/query bytes.
+pub fn parse(input: &str) -> Result<Url, String> {
+    let (scheme, rest) = match split_scheme(input) {
+        Some((scheme, rest)) => {
+            let rest = rest.strip_prefix("//").ok_or_else(|| format!("missing host in URL: {}", input))?;
+            (scheme, rest)
+        }
+        // No scheme at all (e.g. `example.com/path`) means there is no `//`
+        // to strip either — the whole input is already authority + path.
+        None => ("https", input),
+    };
+
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..authority_end];
+    let after_authority = &rest[authority_end..];
+
+    let (host, port) = split_authority(authority)?;
+
+    let (path, query, fragment) = split_path_query_fragment(after_authority);
+    let path = if path.is_empty() {
+        "/".to_string()
+    } else {
+        percent_encode_path(&remove_dot_segments(&path))
+    };
+    let query = query.map(|q| percent_encode_query(&q));
+
+    Ok(Url {
+        scheme: scheme.to_lowercase(),
+        host: host.to_lowercase(),
+        port,
+        path,
+        query,
+        fragment,
+    })
+}
+
+/// Normalize a user-supplied URL into a string ready for `request.json`.
+pub fn normalize(input: &str) -> Result<String, String> {
+    parse(input).map(|u| u.to_string())
+}
+
+/// Resolve `relative` against `base` per the RFC 3986 §5.3 algorithm.
+///
+/// An absolute URL passes through unchanged (beyond normalization); a
+/// network-path reference (`//host/...`) inherits only the base scheme; an
+/// absolute-path reference (`/...`) replaces the path; anything else is
+/// merged against the base path's directory and dot-segments are removed.
+pub fn resolve(base: &str, relative: &str) -> Result<String, String> {
+    let base = parse(base)?;
+
+    if split_scheme(relative).is_some() {
+        return parse(relative).map(|u| u.to_string());
+    }
+
+    if let Some(after_slashes) = relative.strip_prefix("//") {
+        return parse(&format!("{}://{}", base.scheme, after_slashes)).map(|u| u.to_string());
+    }
+
+    let (path, query, fragment) = split_path_query_fragment(relative);
+
+    if path.is_empty() {
+        let target = Url {
+            query: query.or_else(|| base.query.clone()),
+            fragment,
+            ..base
+        };
+        return Ok(target.to_string());
+    }
+
+    let merged_path = if path.starts_with('/') {
+        remove_dot_segments(&path)
+    } else {
+        remove_dot_segments(&merge_paths(&base, &path))
+    };
+
+    let target = Url {
+        path: percent_encode_path(&merged_path),
+        query: query.map(|q| percent_encode_query(&q)),
+        fragment,
+        ..base
+    };
+    Ok(target.to_string())
+}
+
+/// Split `scheme://rest`, returning `None` if `input` has no URL scheme.
+fn split_scheme(input: &str) -> Option<(&str, &str)> {
+    let colon = input.find(':')?;
+    if colon == 0 {
+        return None;
+    }
+    let (scheme, rest) = input.split_at(colon);
+    if !scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.') {
+        return None;
+    }
+    if !rest.starts_with("://") {
+        return None;
+    }
+    Some((scheme, &rest[1..]))
+}
+
+fn split_authority(authority: &str) -> Result<(String, Option<u16>), String> {
+    if authority.is_empty() {
+        return Err("empty host".to_string());
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port_str)) if !host.is_empty() && !port_str.is_empty() && port_str.bytes().all(|b| b.is_ascii_digit()) => {
+            let port = port_str
+                .parse::<u16>()
+                .map_err(|_| format!("invalid port: {}", port_str))?;
+            Ok((host.to_string(), Some(port)))
+        }
+        _ => Ok((authority.to_string(), None)),
+    }
+}
+
+fn split_path_query_fragment(s: &str) -> (String, Option<String>, Option<String>) {
+    let (before_fragment, fragment) = match s.find('#') {
+        Some(i) => (&s[..i], Some(s[i + 1..].to_string())),
+        None => (s, None),
+    };
+    let (path, query) = match before_fragment.find('?') {
+        Some(i) => (&before_fragment[..i], Some(before_fragment[i + 1..].to_string())),
+        None => (before_fragment, None),
+    };
+    (path.to_string(), query, fragment)
+}
+
+/// RFC 3986 §5.2.4 dot-segment removal.
+fn remove_dot_segments(path: &str) -> String {
+    let absolute = path.starts_with('/');
+    let rest = if absolute { &path[1..] } else { path };
+
+    // A `.`/`..` segment that completes the path (rather than being followed
+    // by another segment) leaves a trailing slash behind: RFC 3986 §5.2.4
+    // treats each as consuming its input then outputting an empty final
+    // segment, which `.join("/")` renders as a trailing "/".
+    let segments: Vec<&str> = rest.split('/').collect();
+    let mut output: Vec<&str> = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let is_last = i == segments.len() - 1;
+        match *segment {
+            "." => {
+                if is_last {
+                    output.push("");
+                }
+            }
+            ".." => {
+                output.pop();
+                if is_last {
+                    output.push("");
+                }
+            }
+            other => output.push(other),
+        }
+    }
+
+    let mut result = if absolute { String::from("/") } else { String::new() };
+    result.push_str(&output.join("/"));
+    if result.is_empty() {
+        result.push('/');
+    }
+    result
+}
+
+/// Merge a relative path against the directory of the base path (RFC 3986 §5.3).
+fn merge_paths(base: &Url, relative_path: &str) -> String {
+    if base.has_authority() && base.path.is_empty() {
+        return format!("/{}", relative_path);
+    }
+    match base.path.rfind('/') {
+        Some(i) => format!("{}{}", &base.path[..=i], relative_path),
+        None => relative_path.to_string(),
+    }
+}
+
+fn percent_encode_path(path: &str) -> String {
+    percent_encode(path, |b| {
+        b.is_ascii_alphanumeric()
+            || matches!(
+                b,
+                b'-' | b'.' | b'_' | b'~' | b'/' | b':' | b'@' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b'%'
+            )
+    })
+}
+
+fn percent_encode_query(query: &str) -> String {
+    percent_encode(query, |b| {
+        b.is_ascii_alphanumeric()
+            || matches!(
+                b,
+                b'-' | b'.' | b'_' | b'~' | b'/' | b'?' | b':' | b'@' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b'%'
+            )
+    })
+}
+
+fn percent_encode(s: &str, is_safe: impl Fn(u8) -> bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if is_safe(byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_dot_segments_table() {
+        let cases = [
+            ("/a/b/c", "/a/b/c"),
+            ("/a/b/c/./../../g", "/a/g"),
+            ("/a/b/..", "/a/"),
+            ("/a/b/.", "/a/b/"),
+            ("/a/..", "/"),
+            ("/a/./b", "/a/b"),
+            ("/", "/"),
+            ("", "/"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(remove_dot_segments(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn parse_defaults_scheme_to_https() {
+        let cases = ["example.com", "example.com/path", "www.example.com:8080/p"];
+        for input in cases {
+            let url = parse(input).unwrap_or_else(|e| panic!("{}: {}", input, e));
+            assert_eq!(url.scheme, "https", "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn resolve_table() {
+        let base = "https://a.example/b/c/d;p?q";
+        let cases = [
+            ("g", "https://a.example/b/c/g"),
+            ("./g", "https://a.example/b/c/g"),
+            ("g/", "https://a.example/b/c/g/"),
+            ("/g", "https://a.example/g"),
+            ("//g.example", "https://g.example/"),
+            ("?y", "https://a.example/b/c/d;p?y"),
+            ("g?y", "https://a.example/b/c/g?y"),
+            ("#s", "https://a.example/b/c/d;p?q#s"),
+            ("g#s", "https://a.example/b/c/g#s"),
+            ("..", "https://a.example/b/"),
+            ("../..", "https://a.example/"),
+            ("../../g", "https://a.example/g"),
+        ];
+        for (relative, expected) in cases {
+            let resolved = resolve(base, relative).unwrap_or_else(|e| panic!("{}: {}", relative, e));
+            assert_eq!(resolved, expected, "relative: {}", relative);
+        }
+    }
+}